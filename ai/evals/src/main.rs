@@ -25,11 +25,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let config: EvalsConfig = toml::from_str(&config_text)?;
 
     let cases_text = fs::read_to_string(cases_path)?;
-    let mut cases = Vec::new();
-    for line in cases_text.lines().filter(|line| !line.trim().is_empty()) {
-        let case: EvalCase = serde_json::from_str(line)?;
-        cases.push(case);
-    }
+    let cases = parse_cases(&cases_text)?;
 
     println!(
         "Loaded {} cases for {} (metric: {}, threshold: {})",
@@ -41,3 +37,50 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn parse_cases(text: &str) -> Result<Vec<EvalCase>, Box<dyn Error>> {
+    let mut cases = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        let case: EvalCase =
+            serde_json::from_str(line).map_err(|err| format!("line {}: {}", line_number, err))?;
+        if case.input.trim().is_empty() || case.expected.trim().is_empty() {
+            return Err(
+                format!("line {}: input and expected must not be empty", line_number).into(),
+            );
+        }
+        cases.push(case);
+    }
+    Ok(cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_non_blank_line() {
+        let text = "{\"input\":\"hello\",\"expected\":\"hello\"}\n\n{\"input\":\"goodbye\",\"expected\":\"farewell\"}\n";
+
+        let cases = parse_cases(text).unwrap();
+
+        assert_eq!(cases.len(), 2);
+        assert_eq!(cases[1].input, "goodbye");
+        assert_eq!(cases[1].expected, "farewell");
+    }
+
+    #[test]
+    fn empty_expected_is_reported_with_its_file_line_number() {
+        let text = "{\"input\":\"hello\",\"expected\":\"hello\"}\n\n{\"input\":\"goodbye\",\"expected\":\"\"}\n";
+
+        let err = parse_cases(text).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "line 3: input and expected must not be empty"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+
+    for path in git_watch_paths() {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+
+    let git_sha = env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.trim().is_empty())
+        .or_else(|| git(&["rev-parse", "--short=12", "HEAD"]))
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rustc-env=WEB_GIT_SHA={}", git_sha.trim());
+}
+
+// Cargo treats a missing rerun-if-changed path as always dirty, so only paths
+// that exist are watched. HEAD lives in the (per-worktree) git dir, branch refs
+// in the common dir.
+fn git_watch_paths() -> Vec<PathBuf> {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap_or_default());
+    let mut paths = Vec::new();
+
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        paths.push(manifest_dir.join(git_dir).join("HEAD"));
+    }
+    if let Some(common_dir) = git(&["rev-parse", "--git-common-dir"]) {
+        let common_dir = manifest_dir.join(common_dir);
+        paths.push(common_dir.join("refs").join("heads"));
+        paths.push(common_dir.join("packed-refs"));
+    }
+
+    paths.retain(|path| path.exists());
+    paths
+}
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
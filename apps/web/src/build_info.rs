@@ -0,0 +1,2 @@
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const GIT_SHA: &str = env!("WEB_GIT_SHA");
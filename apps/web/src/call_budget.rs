@@ -0,0 +1,131 @@
+use axum::extract::{MatchedPath, Request, State};
+use axum::middleware::{self, Next};
+use axum::response::Response;
+use axum::Router;
+use core_entities::Greeting;
+use core_ports::{BoxFuture, GreetingRepository, RepoError};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+
+use crate::http::{self, AppState};
+
+tokio::task_local! {
+    static CALLS: RefCell<CallCounts>;
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallCounts {
+    pub per_method: BTreeMap<&'static str, u32>,
+}
+
+impl CallCounts {
+    pub fn total(&self) -> u32 {
+        self.per_method.values().sum()
+    }
+}
+
+fn record(method: &'static str) {
+    let _ = CALLS.try_with(|calls| {
+        *calls.borrow_mut().per_method.entry(method).or_insert(0) += 1;
+    });
+}
+
+pub async fn count_calls<F: Future>(future: F) -> (F::Output, CallCounts) {
+    CALLS
+        .scope(RefCell::new(CallCounts::default()), async move {
+            let output = future.await;
+            let counts = CALLS.with(|calls| calls.take());
+            (output, counts)
+        })
+        .await
+}
+
+#[derive(Clone)]
+pub struct CallBudget {
+    limit: u32,
+    log: Arc<Mutex<dyn Write + Send>>,
+}
+
+impl CallBudget {
+    pub fn new(limit: u32) -> Self {
+        Self::with_log(limit, io::stderr())
+    }
+
+    pub fn with_log(limit: u32, log: impl Write + Send + 'static) -> Self {
+        Self {
+            limit,
+            log: Arc::new(Mutex::new(log)),
+        }
+    }
+}
+
+pub fn instrumented_router(state: AppState, budget: CallBudget) -> Router {
+    let state = AppState {
+        repo: Arc::new(CountingGreetingRepository::new(state.repo)),
+        ..state
+    };
+    http::router(state).layer(middleware::from_fn_with_state(budget, enforce))
+}
+
+pub async fn enforce(State(budget): State<CallBudget>, request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    let (response, counts) = count_calls(next.run(request)).await;
+
+    if counts.total() > budget.limit {
+        let _ = writeln!(
+            budget.log.lock().unwrap(),
+            "warning: repository call budget exceeded route={} calls={} budget={} per_method={:?}",
+            route,
+            counts.total(),
+            budget.limit,
+            counts.per_method
+        );
+    }
+
+    response
+}
+
+pub struct CountingGreetingRepository {
+    inner: Arc<dyn GreetingRepository>,
+}
+
+impl CountingGreetingRepository {
+    pub fn new(inner: Arc<dyn GreetingRepository>) -> Self {
+        Self { inner }
+    }
+}
+
+impl GreetingRepository for CountingGreetingRepository {
+    fn list_greetings<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Greeting>, RepoError>> {
+        record("list_greetings");
+        self.inner.list_greetings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use datastore::MemoryGreetingRepository;
+
+    #[tokio::test]
+    async fn counts_each_repository_call_within_a_request() {
+        let repo = CountingGreetingRepository::new(Arc::new(MemoryGreetingRepository::new(vec![])));
+
+        let (_, counts) = count_calls(async {
+            repo.list_greetings().await.unwrap();
+            repo.list_greetings().await.unwrap();
+        })
+        .await;
+
+        assert_eq!(counts.per_method.get("list_greetings"), Some(&2));
+        assert_eq!(counts.total(), 2);
+    }
+}
@@ -4,13 +4,31 @@ use std::env;
 pub struct Config {
     pub addr: String,
     pub database_url: Option<String>,
+    pub slow_render_threshold_ms: u64,
+    #[cfg(debug_assertions)]
+    pub repo_call_budget: u32,
 }
 
 impl Config {
     pub fn from_env() -> Self {
         let addr = env::var("WEB_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
         let database_url = env::var("DATABASE_URL").ok();
+        let slow_render_threshold_ms = env::var("SLOW_RENDER_THRESHOLD_MS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(50);
+        #[cfg(debug_assertions)]
+        let repo_call_budget = env::var("REPO_CALL_BUDGET")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(10);
 
-        Self { addr, database_url }
+        Self {
+            addr,
+            database_url,
+            slow_render_threshold_ms,
+            #[cfg(debug_assertions)]
+            repo_call_budget,
+        }
     }
 }
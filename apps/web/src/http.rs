@@ -4,22 +4,29 @@ use core_ports::GreetingRepository;
 use std::sync::Arc;
 use tower_http::services::ServeDir;
 
+use crate::render_metrics::RenderMetrics;
 use crate::routes;
 
 #[derive(Clone)]
 pub struct AppState {
     pub repo: Arc<dyn GreetingRepository>,
+    pub render_metrics: Arc<RenderMetrics>,
 }
 
 impl AppState {
-    pub fn new(repo: Arc<dyn GreetingRepository>) -> Self {
-        Self { repo }
+    pub fn new(repo: Arc<dyn GreetingRepository>, render_metrics: Arc<RenderMetrics>) -> Self {
+        Self {
+            repo,
+            render_metrics,
+        }
     }
 }
 
 pub fn router(state: AppState) -> Router {
     Router::new()
         .route("/", get(routes::index))
+        .route("/greetings", get(routes::greetings))
+        .route("/version", get(routes::version))
         .with_state(state)
         .nest_service("/static", ServeDir::new("apps/web/static"))
 }
@@ -0,0 +1,10 @@
+pub mod build_info;
+#[cfg(debug_assertions)]
+pub mod call_budget;
+pub mod config;
+pub mod http;
+pub mod presenters;
+pub mod render_metrics;
+pub mod response_format;
+mod routes;
+pub mod view_models;
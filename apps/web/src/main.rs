@@ -1,18 +1,20 @@
-mod config;
-mod http;
-mod presenters;
-mod routes;
-mod view_models;
-
 use core_entities::Greeting;
 use core_ports::GreetingRepository;
 use datastore::postgres::PostgresGreetingRepository;
 use datastore::MemoryGreetingRepository;
 use std::sync::Arc;
+use std::time::Duration;
+#[cfg(debug_assertions)]
+use web::call_budget;
+use web::config;
+use web::http::AppState;
+use web::render_metrics::RenderMetrics;
 
 #[tokio::main]
 async fn main() {
     let config = config::Config::from_env();
+    let render_metrics = Arc::new(RenderMetrics::new());
+    render_metrics.set_slow_threshold(Duration::from_millis(config.slow_render_threshold_ms));
 
     let repo: Arc<dyn GreetingRepository> = match config.database_url {
         Some(url) => Arc::new(PostgresGreetingRepository::new(url)),
@@ -22,8 +24,15 @@ async fn main() {
         }
     };
 
-    let state = http::AppState::new(repo);
-    let app = http::router(state);
+    let state = AppState::new(repo, render_metrics);
+
+    #[cfg(debug_assertions)]
+    let app = call_budget::instrumented_router(
+        state,
+        call_budget::CallBudget::new(config.repo_call_budget),
+    );
+    #[cfg(not(debug_assertions))]
+    let app = web::http::router(state);
 
     let listener = tokio::net::TcpListener::bind(&config.addr)
         .await
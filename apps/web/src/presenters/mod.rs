@@ -22,6 +22,12 @@ impl GreetingPresenter {
     }
 }
 
+impl Default for GreetingPresenter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GreetingOutputBoundary for GreetingPresenter {
     fn present(&self, output: GreetingOutput) {
         let greetings = output
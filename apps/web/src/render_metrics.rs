@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+const BUCKET_BOUNDS_MS: [u64; 4] = [1, 5, 25, 100];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    FullPage,
+    Fragment,
+}
+
+impl RenderMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            RenderMode::FullPage => "full_page",
+            RenderMode::Fragment => "fragment",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderHistogram {
+    pub count: u64,
+    pub total_micros: u64,
+    pub buckets: [u64; BUCKET_BOUNDS_MS.len() + 1],
+}
+
+impl RenderHistogram {
+    fn observe(&mut self, elapsed: Duration) {
+        let millis = elapsed.as_millis() as u64;
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| millis < *bound)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.count += 1;
+        self.total_micros += elapsed.as_micros() as u64;
+        self.buckets[bucket] += 1;
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RenderMetricsSnapshot {
+    pub per_template: BTreeMap<&'static str, RenderHistogram>,
+    pub full_page_renders: u64,
+    pub fragment_renders: u64,
+    pub render_errors: u64,
+}
+
+pub struct RenderMetrics {
+    per_template: Mutex<BTreeMap<&'static str, RenderHistogram>>,
+    full_page_renders: AtomicU64,
+    fragment_renders: AtomicU64,
+    render_errors: AtomicU64,
+    slow_threshold_nanos: AtomicU64,
+}
+
+impl RenderMetrics {
+    pub fn new() -> Self {
+        Self {
+            per_template: Mutex::new(BTreeMap::new()),
+            full_page_renders: AtomicU64::new(0),
+            fragment_renders: AtomicU64::new(0),
+            render_errors: AtomicU64::new(0),
+            slow_threshold_nanos: AtomicU64::new(Duration::from_millis(50).as_nanos() as u64),
+        }
+    }
+
+    pub fn set_slow_threshold(&self, threshold: Duration) {
+        self.slow_threshold_nanos
+            .store(threshold.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn is_slow(&self, elapsed: Duration) -> bool {
+        elapsed.as_nanos() as u64 > self.slow_threshold_nanos.load(Ordering::Relaxed)
+    }
+
+    pub fn record_render(&self, template: &'static str, mode: RenderMode, elapsed: Duration) {
+        self.per_template
+            .lock()
+            .unwrap()
+            .entry(template)
+            .or_default()
+            .observe(elapsed);
+        let counter = match mode {
+            RenderMode::FullPage => &self.full_page_renders,
+            RenderMode::Fragment => &self.fragment_renders,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self) {
+        self.render_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> RenderMetricsSnapshot {
+        RenderMetricsSnapshot {
+            per_template: self.per_template.lock().unwrap().clone(),
+            full_page_renders: self.full_page_renders.load(Ordering::Relaxed),
+            fragment_renders: self.fragment_renders.load(Ordering::Relaxed),
+            render_errors: self.render_errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for RenderMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_renders_per_template_and_mode() {
+        let metrics = RenderMetrics::new();
+
+        metrics.record_render(
+            "GreetingsTemplate",
+            RenderMode::FullPage,
+            Duration::from_millis(3),
+        );
+        metrics.record_render(
+            "GreetingsFragment",
+            RenderMode::Fragment,
+            Duration::from_millis(200),
+        );
+        metrics.record_render(
+            "GreetingsFragment",
+            RenderMode::Fragment,
+            Duration::from_micros(10),
+        );
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.full_page_renders, 1);
+        assert_eq!(snapshot.fragment_renders, 2);
+        assert_eq!(
+            snapshot.per_template["GreetingsTemplate"].buckets,
+            [0, 1, 0, 0, 0]
+        );
+        assert_eq!(
+            snapshot.per_template["GreetingsFragment"].buckets,
+            [1, 0, 0, 0, 1]
+        );
+        assert_eq!(snapshot.per_template["GreetingsFragment"].count, 2);
+    }
+
+    #[test]
+    fn slow_threshold_is_configurable() {
+        let metrics = RenderMetrics::new();
+        metrics.set_slow_threshold(Duration::from_millis(10));
+
+        assert!(!metrics.is_slow(Duration::from_millis(10)));
+        assert!(metrics.is_slow(Duration::from_millis(11)));
+    }
+}
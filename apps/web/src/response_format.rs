@@ -0,0 +1,344 @@
+use askama::Template;
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::{ACCEPT, VARY};
+use axum::http::request::Parts;
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{Html, IntoResponse, Json, Response};
+use serde::Serialize;
+use std::convert::Infallible;
+use std::io::{self, Write};
+use std::time::Instant;
+
+use crate::render_metrics::{RenderMetrics, RenderMode};
+use crate::view_models::ErrorJson;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Page,
+    Fragment,
+    Json,
+}
+
+impl ResponseFormat {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        if is_htmx_request(headers) && !is_history_restore_request(headers) {
+            return ResponseFormat::Fragment;
+        }
+
+        let accept = headers
+            .get(ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+        let json_quality = media_range_quality(accept, "application/json").unwrap_or(0.0);
+        let html_quality = media_range_quality(accept, "text/html").unwrap_or(0.0);
+
+        if json_quality > 0.0 && json_quality > html_quality {
+            ResponseFormat::Json
+        } else {
+            ResponseFormat::Page
+        }
+    }
+}
+
+fn media_range_quality(accept: &str, media_type: &str) -> Option<f32> {
+    accept.split(',').find_map(|range| {
+        let mut parts = range.split(';').map(str::trim);
+        let range_type = parts.next()?;
+        if !range_type.eq_ignore_ascii_case(media_type) {
+            return None;
+        }
+        let quality = parts
+            .filter_map(|param| param.split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+            .and_then(|(_, value)| value.trim().parse().ok())
+            .unwrap_or(1.0);
+        Some(quality)
+    })
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ResponseFormat
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ResponseFormat::from_headers(&parts.headers))
+    }
+}
+
+pub fn is_htmx_request(headers: &HeaderMap) -> bool {
+    header_is_true(headers, "HX-Request")
+}
+
+// htmx sends this when restoring history after a cache miss and expects the full page.
+fn is_history_restore_request(headers: &HeaderMap) -> bool {
+    header_is_true(headers, "HX-History-Restore-Request")
+}
+
+fn header_is_true(headers: &HeaderMap, name: &str) -> bool {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+pub trait NamedTemplate: Template {
+    const NAME: &'static str;
+}
+
+pub fn respond<P, F, J>(
+    format: ResponseFormat,
+    metrics: &RenderMetrics,
+    page: P,
+    fragment: F,
+    json: J,
+) -> Response
+where
+    P: NamedTemplate,
+    F: NamedTemplate,
+    J: Serialize,
+{
+    respond_with_log(format, metrics, page, fragment, json, &mut io::stderr())
+}
+
+pub fn respond_with_log<P, F, J>(
+    format: ResponseFormat,
+    metrics: &RenderMetrics,
+    page: P,
+    fragment: F,
+    json: J,
+    log: &mut dyn Write,
+) -> Response
+where
+    P: NamedTemplate,
+    F: NamedTemplate,
+    J: Serialize,
+{
+    let response = match format {
+        ResponseFormat::Page => render(page, RenderMode::FullPage, metrics, log),
+        ResponseFormat::Fragment => render(fragment, RenderMode::Fragment, metrics, log),
+        ResponseFormat::Json => Json(json).into_response(),
+    };
+    with_vary(response)
+}
+
+pub fn respond_error(
+    format: ResponseFormat,
+    status: StatusCode,
+    message: &'static str,
+) -> Response {
+    let response = match format {
+        ResponseFormat::Page | ResponseFormat::Fragment => (status, message).into_response(),
+        ResponseFormat::Json => (status, Json(ErrorJson { error: message })).into_response(),
+    };
+    with_vary(response)
+}
+
+fn with_vary(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(VARY, HeaderValue::from_static("HX-Request, Accept"));
+    response
+}
+
+fn render<T: NamedTemplate>(
+    template: T,
+    mode: RenderMode,
+    metrics: &RenderMetrics,
+    log: &mut dyn Write,
+) -> Response {
+    let name = T::NAME;
+    let started = Instant::now();
+
+    match template.render() {
+        Ok(body) => {
+            let elapsed = started.elapsed();
+            metrics.record_render(name, mode, elapsed);
+            if metrics.is_slow(elapsed) {
+                let _ = writeln!(
+                    log,
+                    "warning: slow template render template={} mode={} elapsed_ms={}",
+                    name,
+                    mode.label(),
+                    elapsed.as_millis()
+                );
+            }
+            Html(body).into_response()
+        }
+        Err(err) => {
+            metrics.record_error();
+            let _ = writeln!(
+                log,
+                "error: template render failed template={} error={}",
+                name, err
+            );
+            (StatusCode::INTERNAL_SERVER_ERROR, "template error").into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt;
+    use std::time::Duration;
+
+    struct FailingDisplay;
+
+    impl fmt::Display for FailingDisplay {
+        fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    #[derive(Template)]
+    #[template(source = "<p>{{ value }}</p>", ext = "html")]
+    struct FailingTemplate {
+        value: FailingDisplay,
+    }
+
+    impl NamedTemplate for FailingTemplate {
+        const NAME: &'static str = "FailingTemplate";
+    }
+
+    #[derive(Template)]
+    #[template(source = "<p>{{ value }}</p>", ext = "html")]
+    struct PlainTemplate {
+        value: &'static str,
+    }
+
+    impl NamedTemplate for PlainTemplate {
+        const NAME: &'static str = "PlainTemplate";
+    }
+
+    #[test]
+    fn failed_render_returns_500_counts_the_error_and_logs_the_template() {
+        let metrics = RenderMetrics::new();
+        let mut log = Vec::new();
+
+        let response = respond_with_log(
+            ResponseFormat::Page,
+            &metrics,
+            FailingTemplate {
+                value: FailingDisplay,
+            },
+            FailingTemplate {
+                value: FailingDisplay,
+            },
+            (),
+            &mut log,
+        );
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.render_errors, 1);
+        assert_eq!(snapshot.full_page_renders, 0);
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.contains("template render failed template=FailingTemplate"));
+    }
+
+    #[test]
+    fn render_over_the_slow_threshold_logs_a_warning() {
+        let metrics = RenderMetrics::new();
+        metrics.set_slow_threshold(Duration::ZERO);
+        let mut log = Vec::new();
+
+        let response = respond_with_log(
+            ResponseFormat::Fragment,
+            &metrics,
+            PlainTemplate { value: "page" },
+            PlainTemplate { value: "fragment" },
+            (),
+            &mut log,
+        );
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(metrics.snapshot().fragment_renders, 1);
+        let log = String::from_utf8(log).unwrap();
+        assert!(log.contains("warning: slow template render template=PlainTemplate mode=fragment"));
+    }
+
+    #[test]
+    fn render_within_the_slow_threshold_logs_nothing() {
+        let metrics = RenderMetrics::new();
+        metrics.set_slow_threshold(Duration::from_secs(60));
+        let mut log = Vec::new();
+
+        respond_with_log(
+            ResponseFormat::Page,
+            &metrics,
+            PlainTemplate { value: "page" },
+            PlainTemplate { value: "fragment" },
+            (),
+            &mut log,
+        );
+
+        assert!(log.is_empty());
+    }
+
+    #[test]
+    fn plain_browser_request_gets_the_full_page() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("text/html"));
+
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Page);
+    }
+
+    #[test]
+    fn htmx_request_gets_a_fragment_even_when_json_is_accepted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("HX-Request", HeaderValue::from_static("true"));
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+
+        assert_eq!(
+            ResponseFormat::from_headers(&headers),
+            ResponseFormat::Fragment
+        );
+    }
+
+    #[test]
+    fn htmx_history_restore_gets_the_full_page() {
+        let mut headers = HeaderMap::new();
+        headers.insert("HX-Request", HeaderValue::from_static("true"));
+        headers.insert(
+            "HX-History-Restore-Request",
+            HeaderValue::from_static("true"),
+        );
+
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Page);
+    }
+
+    #[test]
+    fn json_with_zero_quality_is_never_chosen() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("text/html, application/json;q=0"),
+        );
+
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Page);
+    }
+
+    #[test]
+    fn json_is_chosen_only_when_preferred_over_html() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("text/html;q=0.9, application/json"),
+        );
+
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Json);
+    }
+
+    #[test]
+    fn json_accept_header_gets_json() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json"));
+
+        assert_eq!(ResponseFormat::from_headers(&headers), ResponseFormat::Json);
+    }
+}
@@ -1,33 +1,76 @@
 use askama::Template;
 use axum::extract::State;
 use axum::http::StatusCode;
-use axum::response::{Html, IntoResponse};
-use core_use_cases::ListGreetingsInteractor;
+use axum::response::{Json, Redirect, Response};
+use core_use_cases::{ListGreetingsInteractor, UseCaseError};
+use shared::error::error_chain;
 
+use crate::build_info;
 use crate::http::AppState;
 use crate::presenters::GreetingPresenter;
-use crate::view_models::GreetingViewModel;
+use crate::response_format::{respond, respond_error, NamedTemplate, ResponseFormat};
+use crate::view_models::{GreetingListJson, GreetingViewModel, VersionJson};
 
 #[derive(Template)]
-#[template(path = "index.html")]
-struct IndexTemplate {
+#[template(path = "greetings.html")]
+struct GreetingsTemplate {
     vm: GreetingViewModel,
 }
 
-pub async fn index(State(state): State<AppState>) -> impl IntoResponse {
+impl NamedTemplate for GreetingsTemplate {
+    const NAME: &'static str = "GreetingsTemplate";
+}
+
+#[derive(Template)]
+#[template(path = "partials/greetings.html")]
+struct GreetingsFragment {
+    vm: GreetingViewModel,
+}
+
+impl NamedTemplate for GreetingsFragment {
+    const NAME: &'static str = "GreetingsFragment";
+}
+
+pub async fn index() -> Redirect {
+    Redirect::to("/greetings")
+}
+
+pub async fn greetings(State(state): State<AppState>, format: ResponseFormat) -> Response {
     let presenter = GreetingPresenter::new();
     let interactor = ListGreetingsInteractor::new(state.repo.as_ref(), &presenter);
 
     if let Err(err) = interactor.execute().await {
-        eprintln!("use case error: {}", err);
-        return (StatusCode::INTERNAL_SERVER_ERROR, "use case error").into_response();
+        log_use_case_error("greetings", &err);
+        return respond_error(format, StatusCode::INTERNAL_SERVER_ERROR, "use case error");
     }
 
     let view_model = presenter.take_view_model();
-    let template = IndexTemplate { vm: view_model };
+    let json = GreetingListJson {
+        greetings: view_model.greetings.clone(),
+    };
 
-    match template.render() {
-        Ok(body) => Html(body).into_response(),
-        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "template error").into_response(),
-    }
+    respond(
+        format,
+        &state.render_metrics,
+        GreetingsTemplate {
+            vm: view_model.clone(),
+        },
+        GreetingsFragment { vm: view_model },
+        json,
+    )
+}
+
+pub async fn version() -> Json<VersionJson> {
+    Json(VersionJson {
+        version: build_info::VERSION,
+        git_sha: build_info::GIT_SHA,
+    })
+}
+
+fn log_use_case_error(route: &str, err: &UseCaseError) {
+    eprintln!(
+        "error: use case failed route={} error={}",
+        route,
+        error_chain(err)
+    );
 }
@@ -1,3 +1,5 @@
+use serde::Serialize;
+
 #[derive(Clone, Debug)]
 pub struct GreetingViewModel {
     pub title: String,
@@ -15,8 +17,24 @@ impl GreetingViewModel {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct GreetingListItem {
     pub id: u64,
     pub message: String,
 }
+
+#[derive(Clone, Debug, Serialize)]
+pub struct GreetingListJson {
+    pub greetings: Vec<GreetingListItem>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct ErrorJson {
+    pub error: &'static str,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct VersionJson {
+    pub version: &'static str,
+    pub git_sha: &'static str,
+}
@@ -0,0 +1,255 @@
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::response::Response;
+use core_entities::Greeting;
+use core_ports::{BoxFuture, GreetingRepository, RepoError};
+use datastore::MemoryGreetingRepository;
+#[cfg(debug_assertions)]
+use std::io::{self, Write};
+use std::sync::Arc;
+#[cfg(debug_assertions)]
+use std::sync::Mutex;
+use tower::ServiceExt;
+use web::http::{router, AppState};
+use web::render_metrics::RenderMetrics;
+
+struct FailingRepo;
+
+impl GreetingRepository for FailingRepo {
+    fn list_greetings<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Greeting>, RepoError>> {
+        Box::pin(async move { Err(RepoError::new("connection refused")) })
+    }
+}
+
+fn seeded_state() -> AppState {
+    let seed = vec![Greeting::new(1, "Hello from Clean Architecture")];
+    AppState::new(
+        Arc::new(MemoryGreetingRepository::new(seed)),
+        Arc::new(RenderMetrics::new()),
+    )
+}
+
+fn failing_state() -> AppState {
+    AppState::new(Arc::new(FailingRepo), Arc::new(RenderMetrics::new()))
+}
+
+async fn get(state: AppState, uri: &str, headers: &[(&str, &str)]) -> Response {
+    let mut request = Request::builder().uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    router(state)
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body_text(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn browser_visit_renders_the_full_greetings_page() {
+    let response = get(seeded_state(), "/greetings", &[("Accept", "text/html")]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_text(response).await;
+    assert!(body.contains("<html"));
+    assert!(body.contains("Hello from Clean Architecture"));
+}
+
+#[tokio::test]
+async fn htmx_request_renders_only_the_greetings_fragment() {
+    let response = get(seeded_state(), "/greetings", &[("HX-Request", "true")]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = body_text(response).await;
+    assert!(!body.contains("<html"));
+    assert!(body.contains(r#"<section id="greetings">"#));
+    assert!(body.contains("Hello from Clean Architecture"));
+}
+
+#[tokio::test]
+async fn json_client_receives_the_greeting_list() {
+    let response = get(
+        seeded_state(),
+        "/greetings",
+        &[("Accept", "application/json")],
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(json["greetings"][0]["id"], 1);
+    assert_eq!(
+        json["greetings"][0]["message"],
+        "Hello from Clean Architecture"
+    );
+}
+
+#[tokio::test]
+async fn repository_failure_shows_a_generic_error() {
+    let response = get(failing_state(), "/greetings", &[]).await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body = body_text(response).await;
+    assert!(!body.contains("connection refused"));
+}
+
+#[tokio::test]
+async fn every_greetings_representation_varies_on_htmx_and_accept() {
+    let header_sets: [&[(&str, &str)]; 3] = [
+        &[("Accept", "text/html")],
+        &[("HX-Request", "true")],
+        &[("Accept", "application/json")],
+    ];
+
+    for headers in header_sets {
+        let response = get(seeded_state(), "/greetings", headers).await;
+
+        assert_eq!(response.headers()["vary"], "HX-Request, Accept");
+    }
+}
+
+#[tokio::test]
+async fn repository_failure_is_reported_as_json_to_json_clients() {
+    let response = get(
+        failing_state(),
+        "/greetings",
+        &[("Accept", "application/json")],
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(response.headers()["content-type"], "application/json");
+    assert_eq!(response.headers()["vary"], "HX-Request, Accept");
+    let json: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(json["error"], "use case error");
+}
+
+#[tokio::test]
+async fn root_redirects_to_the_greetings_demo() {
+    let response = get(seeded_state(), "/", &[]).await;
+
+    assert!(response.status().is_redirection());
+    assert_eq!(response.headers()["location"], "/greetings");
+}
+
+#[tokio::test]
+async fn page_and_fragment_renders_are_counted_separately() {
+    let state = seeded_state();
+
+    get(state.clone(), "/greetings", &[]).await;
+    get(state.clone(), "/greetings", &[("HX-Request", "true")]).await;
+
+    let snapshot = state.render_metrics.snapshot();
+    assert_eq!(snapshot.full_page_renders, 1);
+    assert_eq!(snapshot.fragment_renders, 1);
+    assert_eq!(snapshot.per_template["GreetingsTemplate"].count, 1);
+    assert_eq!(snapshot.per_template["GreetingsFragment"].count, 1);
+    assert_eq!(snapshot.render_errors, 0);
+}
+
+#[tokio::test]
+async fn version_endpoint_reports_the_running_build() {
+    let response = get(seeded_state(), "/version", &[]).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let json: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    let git_sha = json["git_sha"].as_str().unwrap();
+    assert!(!git_sha.is_empty());
+    let in_git_checkout = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../.git")
+        .exists();
+    if in_git_checkout {
+        assert_ne!(git_sha, "unknown");
+    }
+}
+
+#[cfg(debug_assertions)]
+#[derive(Clone, Default)]
+struct CapturedLog(Arc<Mutex<Vec<u8>>>);
+
+#[cfg(debug_assertions)]
+impl CapturedLog {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[cfg(debug_assertions)]
+impl Write for CapturedLog {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(debug_assertions)]
+async fn get_with_call_budget(limit: u32, log: CapturedLog) -> Response {
+    use web::call_budget::{instrumented_router, CallBudget};
+
+    let app = instrumented_router(seeded_state(), CallBudget::with_log(limit, log));
+    app.oneshot(
+        Request::builder()
+            .uri("/greetings")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await
+    .unwrap()
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test]
+async fn request_over_the_call_budget_logs_a_warning_naming_the_route() {
+    let log = CapturedLog::default();
+
+    let response = get_with_call_budget(0, log.clone()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let log = log.contents();
+    assert!(log.contains("repository call budget exceeded route=/greetings calls=1 budget=0"));
+    assert!(log.contains("\"list_greetings\": 1"));
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test]
+async fn request_at_the_call_budget_stays_silent() {
+    let log = CapturedLog::default();
+
+    let response = get_with_call_budget(1, log.clone()).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(log.contents(), "");
+}
+
+#[cfg(debug_assertions)]
+#[tokio::test]
+async fn call_counts_reset_between_requests() {
+    use web::call_budget::{instrumented_router, CallBudget};
+
+    let log = CapturedLog::default();
+    let app = instrumented_router(seeded_state(), CallBudget::with_log(1, log.clone()));
+
+    for _ in 0..2 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/greetings")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    assert_eq!(log.contents(), "");
+}
@@ -19,6 +19,14 @@ impl RepoError {
     }
 }
 
+impl std::fmt::Display for RepoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RepoError {}
+
 pub trait GreetingRepository: Send + Sync {
     fn list_greetings<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Greeting>, RepoError>>;
 }
@@ -10,12 +10,18 @@ pub enum UseCaseError {
 impl std::fmt::Display for UseCaseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            UseCaseError::Repo(err) => write!(f, "repository error: {}", err.message),
+            UseCaseError::Repo(_) => write!(f, "repository error"),
         }
     }
 }
 
-impl std::error::Error for UseCaseError {}
+impl std::error::Error for UseCaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            UseCaseError::Repo(err) => Some(err),
+        }
+    }
+}
 
 pub struct ListGreetingsInteractor<'a> {
     repo: &'a dyn GreetingRepository,
@@ -71,3 +71,23 @@ fn list_greetings_sends_output_to_presenter() {
     assert_eq!(output.greetings.len(), 1);
     assert_eq!(output.greetings[0].message, "Hello");
 }
+
+struct FailingRepo;
+
+impl GreetingRepository for FailingRepo {
+    fn list_greetings<'a>(&'a self) -> BoxFuture<'a, Result<Vec<Greeting>, RepoError>> {
+        Box::pin(async move { Err(RepoError::new("connection refused")) })
+    }
+}
+
+#[test]
+fn repository_failure_is_exposed_as_the_error_source() {
+    let repo = FailingRepo;
+    let presenter = CapturingPresenter::new();
+    let interactor = ListGreetingsInteractor::new(&repo, &presenter);
+
+    let err = block_on(interactor.execute()).expect_err("use case should fail");
+
+    let source = std::error::Error::source(&err).expect("error should have a source");
+    assert_eq!(source.to_string(), "connection refused");
+}
@@ -18,3 +18,14 @@ impl std::fmt::Display for AppError {
 }
 
 impl std::error::Error for AppError {}
+
+pub fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
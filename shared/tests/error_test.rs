@@ -0,0 +1,34 @@
+use shared::error::{error_chain, AppError};
+
+#[derive(Debug)]
+struct Wrapper {
+    inner: AppError,
+}
+
+impl std::fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "outer failure")
+    }
+}
+
+impl std::error::Error for Wrapper {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+#[test]
+fn error_chain_joins_every_source_in_order() {
+    let err = Wrapper {
+        inner: AppError::new("connection refused"),
+    };
+
+    assert_eq!(error_chain(&err), "outer failure: connection refused");
+}
+
+#[test]
+fn error_chain_of_a_leaf_error_is_its_message() {
+    let err = AppError::new("connection refused");
+
+    assert_eq!(error_chain(&err), "connection refused");
+}